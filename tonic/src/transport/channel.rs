@@ -0,0 +1,323 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use http::Uri;
+use tokio::net::TcpStream;
+use tower::buffer::Buffer;
+use tower::limit::{ConcurrencyLimitLayer, RateLimitLayer};
+use tower::timeout::TimeoutLayer;
+use tower::util::BoxService;
+use tower::{BoxError, Service, ServiceBuilder};
+
+use super::Error;
+#[cfg(feature = "_tls-any")]
+use super::tls::{Certificate, Identity};
+
+/// Bound on the number of requests a [`Channel`] will queue up while the
+/// configured timeout, rate-limit and concurrency-limit layers (and the
+/// underlying connection) work through the backlog. Matches the bound used
+/// elsewhere in the ecosystem for this kind of `tower::buffer::Buffer`.
+const DEFAULT_BUFFER_SIZE: usize = 1024;
+
+type BoxedChannelService = BoxService<
+    http::Request<crate::body::BoxBody>,
+    http::Response<crate::body::BoxBody>,
+    BoxError,
+>;
+
+/// A channel to a remote gRPC endpoint, built from an [`Endpoint`].
+#[derive(Clone)]
+pub struct Channel {
+    svc: Buffer<BoxedChannelService, http::Request<crate::body::BoxBody>>,
+}
+
+/// A builder for configuring and creating a [`Channel`].
+#[derive(Clone)]
+pub struct Endpoint {
+    uri: Uri,
+    #[cfg(feature = "_tls-any")]
+    tls: Option<ClientTlsConfig>,
+    timeout: Option<Duration>,
+    rate_limit: Option<(u64, Duration)>,
+    concurrency_limit: Option<usize>,
+}
+
+impl Endpoint {
+    /// Create a new `Endpoint` from a static string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string cannot be parsed as a [`Uri`].
+    pub fn from_static(s: &'static str) -> Self {
+        let uri = Uri::from_static(s);
+        Endpoint {
+            uri,
+            #[cfg(feature = "_tls-any")]
+            tls: None,
+            timeout: None,
+            rate_limit: None,
+            concurrency_limit: None,
+        }
+    }
+
+    /// Configure TLS for this endpoint.
+    #[cfg(feature = "_tls-any")]
+    pub fn tls_config(self, tls_config: ClientTlsConfig) -> Result<Self, Error> {
+        Ok(Endpoint {
+            tls: Some(tls_config),
+            ..self
+        })
+    }
+
+    /// Apply a timeout to each request.
+    pub fn timeout(self, dur: Duration) -> Self {
+        Endpoint {
+            timeout: Some(dur),
+            ..self
+        }
+    }
+
+    /// Apply a rate limit to each request.
+    pub fn rate_limit(self, limit: u64, duration: Duration) -> Self {
+        Endpoint {
+            rate_limit: Some((limit, duration)),
+            ..self
+        }
+    }
+
+    /// Apply a concurrency limit to each request.
+    pub fn concurrency_limit(self, limit: usize) -> Self {
+        Endpoint {
+            concurrency_limit: Some(limit),
+            ..self
+        }
+    }
+
+    /// Connect to the configured endpoint, resolving TLS settings and
+    /// wrapping the resulting connection with the configured timeout,
+    /// rate-limit and concurrency-limit layers, into the resulting
+    /// [`Channel`].
+    ///
+    /// Opens a TCP connection to the endpoint's authority, performs the
+    /// configured TLS handshake over it (if any), and then the HTTP/2
+    /// client preface handshake, so that by the time this returns the
+    /// channel is ready to dispatch requests.
+    pub async fn connect(&self) -> Result<Channel, Error> {
+        let authority = self
+            .uri
+            .authority()
+            .ok_or_else(|| Error::from_source("endpoint uri has no authority"))?;
+        let host = authority.host();
+        let port = authority
+            .port_u16()
+            .unwrap_or(if self.is_tls() { 443 } else { 80 });
+
+        let tcp = TcpStream::connect((host, port))
+            .await
+            .map_err(Error::from_source)?;
+
+        #[cfg(feature = "_tls-any")]
+        let io: Box<dyn super::service::IoStream> = if let Some(tls) = &self.tls {
+            let peer_addr = tcp.peer_addr().ok();
+            super::tls::build_connector(tls)?
+                .handshake(tcp, peer_addr)
+                .await?
+        } else {
+            Box::new(tcp)
+        };
+
+        #[cfg(not(feature = "_tls-any"))]
+        let io: Box<dyn super::service::IoStream> = Box::new(tcp);
+
+        let (send_request, connection) = hyper::client::conn::Builder::new()
+            .http2_only(true)
+            .handshake(io)
+            .await
+            .map_err(Error::new_other)?;
+
+        tokio::spawn(async move {
+            let _ = connection.await;
+        });
+
+        let raw = SendRequestService {
+            uri: self.uri.clone(),
+            send_request,
+        };
+
+        let svc = ServiceBuilder::new()
+            .option_layer(self.timeout.map(TimeoutLayer::new))
+            .option_layer(
+                self.rate_limit
+                    .map(|(limit, duration)| RateLimitLayer::new(limit, duration)),
+            )
+            .option_layer(self.concurrency_limit.map(ConcurrencyLimitLayer::new))
+            .service(raw);
+
+        Ok(Channel {
+            svc: Buffer::new(BoxService::new(svc), DEFAULT_BUFFER_SIZE),
+        })
+    }
+
+    #[cfg(feature = "_tls-any")]
+    fn is_tls(&self) -> bool {
+        self.tls.is_some()
+    }
+
+    #[cfg(not(feature = "_tls-any"))]
+    fn is_tls(&self) -> bool {
+        false
+    }
+}
+
+impl Channel {
+    /// Create a new `Endpoint` from a static string, ready for configuration.
+    pub fn from_static(s: &'static str) -> Endpoint {
+        Endpoint::from_static(s)
+    }
+}
+
+impl Service<http::Request<crate::body::BoxBody>> for Channel {
+    type Response = http::Response<crate::body::BoxBody>;
+    type Error = Error;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.svc.poll_ready(cx).map_err(Error::new_other)
+    }
+
+    fn call(&mut self, req: http::Request<crate::body::BoxBody>) -> Self::Future {
+        let future = self.svc.call(req);
+        Box::pin(async move { future.await.map_err(Error::new_other) })
+    }
+}
+
+/// The raw, unwrapped HTTP/2 connection handle for a [`Channel`], before the
+/// configured timeout, rate-limit and concurrency-limit layers are applied
+/// around it in [`Endpoint::connect`].
+#[derive(Clone)]
+struct SendRequestService {
+    uri: Uri,
+    send_request: hyper::client::conn::SendRequest<crate::body::BoxBody>,
+}
+
+impl Service<http::Request<crate::body::BoxBody>> for SendRequestService {
+    type Response = http::Response<crate::body::BoxBody>;
+    type Error = BoxError;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.send_request.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, mut req: http::Request<crate::body::BoxBody>) -> Self::Future {
+        if req.uri().authority().is_none() {
+            let mut parts = req.uri().clone().into_parts();
+            parts.scheme = self.uri.scheme().cloned();
+            parts.authority = self.uri.authority().cloned();
+            if let Ok(uri) = http::Uri::from_parts(parts) {
+                *req.uri_mut() = uri;
+            }
+        }
+
+        let future = self.send_request.send_request(req);
+        Box::pin(async move {
+            future
+                .await
+                .map(|res| res.map(crate::body::boxed))
+                .map_err(Into::into)
+        })
+    }
+}
+
+/// Configuration for TLS connections established from a [`Channel`].
+#[cfg(feature = "_tls-any")]
+#[derive(Clone, Default)]
+pub struct ClientTlsConfig {
+    pub(crate) domain: Option<String>,
+    pub(crate) ca_certs: Vec<Certificate>,
+    pub(crate) identity: Option<Identity>,
+    pub(crate) with_webpki_roots: bool,
+    pub(crate) with_native_roots: bool,
+    #[cfg(feature = "_tls-rustls-any")]
+    pub(crate) rustls_client_config: Option<std::sync::Arc<tokio_rustls::rustls::ClientConfig>>,
+}
+
+#[cfg(feature = "_tls-any")]
+impl ClientTlsConfig {
+    /// Creates a new `ClientTlsConfig`.
+    pub fn new() -> Self {
+        ClientTlsConfig::default()
+    }
+
+    /// Sets the CA certificate used to verify the server's certificate.
+    ///
+    /// May be called multiple times to trust more than one root, and
+    /// combined freely with [`with_webpki_roots`](Self::with_webpki_roots)
+    /// and [`with_native_roots`](Self::with_native_roots).
+    pub fn ca_certificate(mut self, ca_certificate: Certificate) -> Self {
+        self.ca_certs.push(ca_certificate);
+        self
+    }
+
+    /// Trust the Mozilla-curated root certificates compiled into the
+    /// `webpki-roots` crate, in addition to any roots added via
+    /// [`ca_certificate`](Self::ca_certificate).
+    ///
+    /// This avoids a frequent source of "unknown issuer" errors when
+    /// talking to public gRPC endpoints, without requiring the operating
+    /// system to have an up to date certificate store.
+    ///
+    /// Only supported on the `rustls` backend: `webpki-roots` ships trust
+    /// anchors, not full certificates, which OpenSSL's certificate store
+    /// cannot consume. [`connect`](Endpoint::connect) returns an error if
+    /// this is set while the `tls-openssl` backend is active; use
+    /// [`with_native_roots`](Self::with_native_roots) or
+    /// [`ca_certificate`](Self::ca_certificate) there instead.
+    pub fn with_webpki_roots(mut self) -> Self {
+        self.with_webpki_roots = true;
+        self
+    }
+
+    /// Trust the certificates found in the operating system's native
+    /// certificate store, in addition to any roots added via
+    /// [`ca_certificate`](Self::ca_certificate).
+    pub fn with_native_roots(mut self) -> Self {
+        self.with_native_roots = true;
+        self
+    }
+
+    /// Sets the identity (certificate + private key) presented to the
+    /// server for client certificate authentication.
+    pub fn identity(mut self, identity: Identity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Sets the domain name used for SNI and certificate verification.
+    pub fn domain_name(mut self, domain_name: impl Into<String>) -> Self {
+        self.domain = Some(domain_name.into());
+        self
+    }
+
+    /// Builds a `ClientTlsConfig` from a fully-constructed
+    /// [`rustls::ClientConfig`](tokio_rustls::rustls::ClientConfig),
+    /// bypassing the builder above entirely. Use this when the builder's
+    /// surface doesn't cover what's needed, e.g. custom certificate
+    /// verifiers, ALPN ordering, or session resumption tuning.
+    ///
+    /// `domain_name` is still required for SNI and is set the same way as
+    /// with the builder.
+    #[cfg(feature = "_tls-rustls-any")]
+    pub fn rustls_client_config(
+        config: std::sync::Arc<tokio_rustls::rustls::ClientConfig>,
+    ) -> Self {
+        ClientTlsConfig {
+            rustls_client_config: Some(config),
+            ..ClientTlsConfig::default()
+        }
+    }
+}
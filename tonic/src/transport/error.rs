@@ -0,0 +1,82 @@
+use std::error::Error as StdError;
+use std::fmt;
+
+#[cfg(feature = "_tls-any")]
+use super::tls::TlsError;
+
+/// Error's that originate from the client or server.
+#[derive(Debug)]
+pub struct Error {
+    inner: ErrorImpl,
+}
+
+#[derive(Debug)]
+enum ErrorImpl {
+    Connect(Box<dyn StdError + Send + Sync>),
+    #[cfg(feature = "_tls-any")]
+    Tls(TlsError),
+    Other(Box<dyn StdError + Send + Sync>),
+}
+
+impl Error {
+    pub(crate) fn from_source(source: impl Into<Box<dyn StdError + Send + Sync>>) -> Self {
+        Error {
+            inner: ErrorImpl::Connect(source.into()),
+        }
+    }
+
+    #[cfg(feature = "_tls-any")]
+    pub(crate) fn from_tls_error(error: TlsError) -> Self {
+        Error {
+            inner: ErrorImpl::Tls(error),
+        }
+    }
+
+    pub(crate) fn new_other(source: impl Into<Box<dyn StdError + Send + Sync>>) -> Self {
+        Error {
+            inner: ErrorImpl::Other(source.into()),
+        }
+    }
+
+    /// Returns the underlying [`TlsError`] if this error is a TLS failure
+    /// (a failed handshake, or a configured certificate or key that could
+    /// not be loaded), so servers can distinguish a misconfigured client
+    /// certificate from a plain I/O error (timeout, reset, ...) instead of
+    /// treating every connection error the same way.
+    #[cfg(feature = "_tls-any")]
+    pub fn tls_error(&self) -> Option<&TlsError> {
+        match &self.inner {
+            ErrorImpl::Tls(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.inner {
+            ErrorImpl::Connect(e) => write!(f, "transport error: {e}"),
+            #[cfg(feature = "_tls-any")]
+            ErrorImpl::Tls(e) => write!(f, "transport tls error: {e}"),
+            ErrorImpl::Other(e) => write!(f, "transport error: {e}"),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match &self.inner {
+            ErrorImpl::Connect(e) => Some(e.as_ref()),
+            #[cfg(feature = "_tls-any")]
+            ErrorImpl::Tls(e) => Some(e),
+            ErrorImpl::Other(e) => Some(e.as_ref()),
+        }
+    }
+}
+
+#[cfg(feature = "_tls-any")]
+impl From<TlsError> for Error {
+    fn from(error: TlsError) -> Self {
+        Error::from_tls_error(error)
+    }
+}
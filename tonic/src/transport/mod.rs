@@ -2,12 +2,15 @@
 //!
 //! This module provides a set of batteries included, fully featured and
 //! fast set of HTTP/2 server and client's. These components each provide a
-//! `rustls` tls backend when the respective feature flag is enabled, and
-//! provides builders to configure transport behavior.
+//! TLS backend when the respective feature flag is enabled, and provides
+//! builders to configure transport behavior. Two TLS backends are
+//! available: [rustls] (the default) and, via the `tls-openssl` feature,
+//! OpenSSL for deployments that need to link against a specific OpenSSL
+//! build.
 //!
 //! # Features
 //!
-//! - TLS support via [rustls].
+//! - TLS support via [rustls] or OpenSSL.
 //! - Load balancing
 //! - Timeouts
 //! - Concurrency Limits
@@ -106,13 +109,15 @@ pub use self::error::Error;
 #[doc(inline)]
 #[cfg(feature = "server")]
 pub use self::server::Server;
+
+pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
 /// Deprecated. Please use [`crate::status::TimeoutExpired`] instead.
 pub use crate::status::TimeoutExpired;
 
 #[cfg(feature = "_tls-any")]
 pub use self::tls::Certificate;
 pub use hyper::{body::Body, Uri};
-#[cfg(feature = "_tls-any")]
+#[cfg(feature = "_tls-rustls-any")]
 pub use tokio_rustls::rustls::pki_types::CertificateDer;
 
 #[cfg(all(feature = "channel", feature = "_tls-any"))]
@@ -121,3 +126,7 @@ pub use self::channel::ClientTlsConfig;
 pub use self::server::ServerTlsConfig;
 #[cfg(feature = "_tls-any")]
 pub use self::tls::Identity;
+#[cfg(feature = "_tls-any")]
+pub use self::tls::TlsError;
+#[cfg(feature = "_tls-any")]
+pub use self::tls::PeerCertificates;
@@ -0,0 +1,260 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use tokio::net::TcpListener;
+use tower::limit::ConcurrencyLimitLayer;
+use tower::{Service, ServiceBuilder};
+
+use super::Error;
+#[cfg(feature = "_tls-any")]
+use super::tls::{Certificate, Identity};
+
+/// Lower and upper bounds for the exponential backoff applied to repeated
+/// [`TcpListener::accept`] errors in [`Router::serve`], so a persistent
+/// accept failure (e.g. file descriptor exhaustion) sleeps and retries
+/// instead of busy-looping the task.
+const MIN_ACCEPT_BACKOFF: Duration = Duration::from_millis(5);
+const MAX_ACCEPT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// A builder for configuring and starting a gRPC server.
+#[derive(Default)]
+pub struct Server {
+    #[cfg(feature = "_tls-any")]
+    tls: Option<ServerTlsConfig>,
+    concurrency_limit_per_connection: Option<usize>,
+}
+
+impl Server {
+    /// Create a new server builder.
+    pub fn builder() -> Self {
+        Server::default()
+    }
+
+    /// Configure TLS for this server.
+    #[cfg(feature = "_tls-any")]
+    pub fn tls_config(mut self, tls_config: ServerTlsConfig) -> Result<Self, Error> {
+        self.tls = Some(tls_config);
+        Ok(self)
+    }
+
+    /// Limit the number of in-flight requests per connection.
+    pub fn concurrency_limit_per_connection(mut self, limit: usize) -> Self {
+        self.concurrency_limit_per_connection = Some(limit);
+        self
+    }
+
+    /// Register a service to be served.
+    pub fn add_service<S>(self, svc: S) -> Router<S>
+    where
+        S: Service<http::Request<crate::body::BoxBody>> + crate::server::NamedService,
+    {
+        Router { server: self, svc }
+    }
+}
+
+/// A [`Server`] with at least one service registered, ready to [`serve`](Router::serve).
+pub struct Router<S> {
+    server: Server,
+    svc: S,
+}
+
+impl<S> Router<S>
+where
+    S: Service<http::Request<crate::body::BoxBody>, Response = http::Response<crate::body::BoxBody>>
+        + crate::server::NamedService
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    /// Bind and serve the registered service on `addr`.
+    ///
+    /// Accepts connections in a loop, performing the configured TLS
+    /// handshake (if any) on each one before driving HTTP/2 traffic to the
+    /// registered service. When [`ServerTlsConfig::client_ca_root`] is set,
+    /// the verified client certificate chain from each connection's
+    /// handshake is inserted into every request's
+    /// [`extensions`](crate::Request::extensions) as
+    /// [`PeerCertificates`](super::tls::PeerCertificates).
+    pub async fn serve(self, addr: SocketAddr) -> Result<(), Error> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .map_err(Error::from_source)?;
+
+        #[cfg(feature = "_tls-any")]
+        let acceptor = self
+            .server
+            .tls
+            .as_ref()
+            .map(super::tls::build_acceptor)
+            .transpose()?;
+
+        let mut backoff = MIN_ACCEPT_BACKOFF;
+
+        loop {
+            let (tcp, peer_addr) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(_) => {
+                    // A persistent accept error (e.g. fd exhaustion) would
+                    // otherwise busy-loop this task forever; back off with
+                    // a capped exponential delay instead, like most
+                    // production accept loops do.
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_ACCEPT_BACKOFF);
+                    continue;
+                }
+            };
+            backoff = MIN_ACCEPT_BACKOFF;
+
+            let svc = self.svc.clone();
+            let concurrency_limit = self.server.concurrency_limit_per_connection;
+
+            #[cfg(feature = "_tls-any")]
+            let acceptor = acceptor.clone();
+
+            tokio::spawn(async move {
+                #[cfg(feature = "_tls-any")]
+                let (io, peer_certificates) = if let Some(acceptor) = &acceptor {
+                    match acceptor.handshake(tcp, Some(peer_addr)).await {
+                        Ok((io, peer_certificates)) => (io, peer_certificates),
+                        Err(_) => return,
+                    }
+                } else {
+                    (super::service::ServerIo::new_plain(tcp), None)
+                };
+
+                #[cfg(not(feature = "_tls-any"))]
+                let io = super::service::ServerIo::new_plain(tcp);
+
+                let svc = ServiceBuilder::new()
+                    .option_layer(concurrency_limit.map(ConcurrencyLimitLayer::new))
+                    .service(svc);
+
+                let svc = WithPeerCertificates {
+                    inner: svc,
+                    #[cfg(feature = "_tls-any")]
+                    peer_certificates,
+                };
+
+                let _ = hyper::server::conn::Http::new()
+                    .http2_only(true)
+                    .serve_connection(io, svc)
+                    .await;
+            });
+        }
+    }
+}
+
+/// Wraps a registered service to insert the connection's verified client
+/// certificate chain, if any, into every request's extensions before
+/// dispatch.
+struct WithPeerCertificates<S> {
+    inner: S,
+    #[cfg(feature = "_tls-any")]
+    peer_certificates: Option<super::tls::PeerCertificates>,
+}
+
+impl<S: Clone> Clone for WithPeerCertificates<S> {
+    fn clone(&self) -> Self {
+        WithPeerCertificates {
+            inner: self.inner.clone(),
+            #[cfg(feature = "_tls-any")]
+            peer_certificates: self.peer_certificates.clone(),
+        }
+    }
+}
+
+impl<S> hyper::service::Service<http::Request<hyper::Body>> for WithPeerCertificates<S>
+where
+    S: Service<http::Request<crate::body::BoxBody>, Response = http::Response<crate::body::BoxBody>>,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    type Response = http::Response<crate::body::BoxBody>;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<hyper::Body>) -> Self::Future {
+        let mut req = req.map(crate::body::boxed);
+
+        #[cfg(feature = "_tls-any")]
+        if let Some(peer_certificates) = &self.peer_certificates {
+            req.extensions_mut().insert(peer_certificates.clone());
+        }
+
+        self.inner.call(req)
+    }
+}
+
+/// Configuration for TLS connections accepted by a [`Server`].
+#[cfg(feature = "_tls-any")]
+#[derive(Clone, Default)]
+pub struct ServerTlsConfig {
+    pub(crate) identity: Option<Identity>,
+    pub(crate) client_ca_root: Option<Certificate>,
+    pub(crate) client_auth_optional: bool,
+    #[cfg(feature = "_tls-rustls-any")]
+    pub(crate) rustls_server_config: Option<std::sync::Arc<tokio_rustls::rustls::ServerConfig>>,
+}
+
+#[cfg(feature = "_tls-any")]
+impl ServerTlsConfig {
+    /// Creates a new `ServerTlsConfig`.
+    pub fn new() -> Self {
+        ServerTlsConfig::default()
+    }
+
+    /// Sets the identity (certificate + private key) presented to clients.
+    pub fn identity(mut self, identity: Identity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Requires clients to present a certificate signed by `cert`, turning
+    /// this server into a mutual TLS endpoint. The verified chain is made
+    /// available to services via
+    /// [`Request::extensions`](crate::Request::extensions) as
+    /// [`PeerCertificates`](super::tls::PeerCertificates).
+    ///
+    /// Combine with [`client_auth_optional`](Self::client_auth_optional) to
+    /// accept, rather than require, a client certificate.
+    pub fn client_ca_root(mut self, cert: Certificate) -> Self {
+        self.client_ca_root = Some(cert);
+        self
+    }
+
+    /// Controls whether a client certificate is required once
+    /// [`client_ca_root`](Self::client_ca_root) is set. Defaults to
+    /// `false` (required). When `true`, clients may connect without a
+    /// certificate; handlers can check
+    /// [`Request::extensions`](crate::Request::extensions) for
+    /// [`PeerCertificates`](super::tls::PeerCertificates) to see whether
+    /// one was presented.
+    pub fn client_auth_optional(mut self, optional: bool) -> Self {
+        self.client_auth_optional = optional;
+        self
+    }
+
+    /// Builds a `ServerTlsConfig` from a fully-constructed
+    /// [`rustls::ServerConfig`](tokio_rustls::rustls::ServerConfig),
+    /// bypassing the builder above entirely. Use this when the builder's
+    /// surface doesn't cover what's needed, e.g. custom certificate
+    /// verifiers, ALPN ordering, session resumption tuning, or OCSP
+    /// stapling.
+    #[cfg(feature = "_tls-rustls-any")]
+    pub fn rustls_server_config(
+        config: std::sync::Arc<tokio_rustls::rustls::ServerConfig>,
+    ) -> Self {
+        ServerTlsConfig {
+            rustls_server_config: Some(config),
+            ..ServerTlsConfig::default()
+        }
+    }
+}
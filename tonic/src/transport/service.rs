@@ -0,0 +1,94 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Any I/O stream a [`Channel`](super::Channel) or [`Server`](super::Server)
+/// can drive HTTP/2 over, regardless of whether it's a raw TCP socket or a
+/// TLS stream from either backend.
+pub(crate) trait IoStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> IoStream for T {}
+
+/// The I/O stream accepted on a server connection.
+///
+/// In the plain-text case this is just the raw TCP stream; when a TLS
+/// backend is enabled it is whichever of that backend's stream types
+/// completed the handshake.
+pub(crate) enum ServerIo<IO> {
+    Plain(IO),
+    #[cfg(feature = "_tls-rustls-any")]
+    Rustls(Box<tokio_rustls::server::TlsStream<IO>>),
+    #[cfg(feature = "tls-openssl")]
+    OpenSsl(Box<tokio_openssl::SslStream<IO>>),
+}
+
+impl<IO> ServerIo<IO> {
+    pub(crate) fn new_plain(io: IO) -> Self {
+        ServerIo::Plain(io)
+    }
+
+    #[cfg(feature = "_tls-rustls-any")]
+    pub(crate) fn new_rustls(io: tokio_rustls::server::TlsStream<IO>) -> Self {
+        ServerIo::Rustls(Box::new(io))
+    }
+
+    #[cfg(feature = "tls-openssl")]
+    pub(crate) fn new_openssl(io: tokio_openssl::SslStream<IO>) -> Self {
+        ServerIo::OpenSsl(Box::new(io))
+    }
+}
+
+impl<IO: AsyncRead + AsyncWrite + Unpin> AsyncRead for ServerIo<IO> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            ServerIo::Plain(io) => Pin::new(io).poll_read(cx, buf),
+            #[cfg(feature = "_tls-rustls-any")]
+            ServerIo::Rustls(io) => Pin::new(io.as_mut()).poll_read(cx, buf),
+            #[cfg(feature = "tls-openssl")]
+            ServerIo::OpenSsl(io) => Pin::new(io.as_mut()).poll_read(cx, buf),
+        }
+    }
+}
+
+impl<IO: AsyncRead + AsyncWrite + Unpin> AsyncWrite for ServerIo<IO> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        match self.get_mut() {
+            ServerIo::Plain(io) => Pin::new(io).poll_write(cx, buf),
+            #[cfg(feature = "_tls-rustls-any")]
+            ServerIo::Rustls(io) => Pin::new(io.as_mut()).poll_write(cx, buf),
+            #[cfg(feature = "tls-openssl")]
+            ServerIo::OpenSsl(io) => Pin::new(io.as_mut()).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            ServerIo::Plain(io) => Pin::new(io).poll_flush(cx),
+            #[cfg(feature = "_tls-rustls-any")]
+            ServerIo::Rustls(io) => Pin::new(io.as_mut()).poll_flush(cx),
+            #[cfg(feature = "tls-openssl")]
+            ServerIo::OpenSsl(io) => Pin::new(io.as_mut()).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            ServerIo::Plain(io) => Pin::new(io).poll_shutdown(cx),
+            #[cfg(feature = "_tls-rustls-any")]
+            ServerIo::Rustls(io) => Pin::new(io.as_mut()).poll_shutdown(cx),
+            #[cfg(feature = "tls-openssl")]
+            ServerIo::OpenSsl(io) => Pin::new(io.as_mut()).poll_shutdown(cx),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::net::SocketAddr;
+
+/// A TLS-specific failure, distinguished from generic I/O errors so servers
+/// can decide what to do about a handshake failure (log and move on, alert,
+/// etc) instead of treating every connection error the same way.
+#[derive(Debug)]
+pub enum TlsError {
+    /// The TLS handshake itself failed, for a reason not covered by a more
+    /// specific variant below.
+    HandshakeFailed {
+        peer: Option<SocketAddr>,
+        source: Box<dyn StdError + Send + Sync>,
+    },
+    /// A configured certificate (a CA root or an identity certificate) could
+    /// not be parsed or loaded. A peer certificate that cannot be parsed is
+    /// reported as [`HandshakeFailed`](Self::HandshakeFailed).
+    InvalidCertificate {
+        source: Box<dyn StdError + Send + Sync>,
+    },
+    /// A configured private key could not be parsed or loaded. With the
+    /// `tls-openssl` backend this includes a key that does not match its
+    /// certificate; the `rustls` backend reports that case as
+    /// [`InvalidCertificate`](Self::InvalidCertificate).
+    InvalidPrivateKey {
+        source: Box<dyn StdError + Send + Sync>,
+    },
+    /// The peer's certificate had expired (or was not yet valid) at the
+    /// time of the handshake.
+    CertificateExpired { peer: Option<SocketAddr> },
+    /// The peer's certificate was not signed by a trusted root.
+    UnknownIssuer { peer: Option<SocketAddr> },
+    /// Mutual TLS was configured as required and the client did not
+    /// present a certificate.
+    ClientCertRequired { peer: Option<SocketAddr> },
+}
+
+impl TlsError {
+    /// The peer's address, when the failure happened after the connection
+    /// was accepted and the address was known.
+    pub fn peer_addr(&self) -> Option<SocketAddr> {
+        match self {
+            TlsError::HandshakeFailed { peer, .. } => *peer,
+            TlsError::InvalidCertificate { .. } => None,
+            TlsError::InvalidPrivateKey { .. } => None,
+            TlsError::CertificateExpired { peer } => *peer,
+            TlsError::UnknownIssuer { peer } => *peer,
+            TlsError::ClientCertRequired { peer } => *peer,
+        }
+    }
+}
+
+impl fmt::Display for TlsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TlsError::HandshakeFailed { peer, source } => {
+                write!(f, "TLS handshake failed")?;
+                if let Some(peer) = peer {
+                    write!(f, " with {peer}")?;
+                }
+                write!(f, ": {source}")
+            }
+            TlsError::InvalidCertificate { source } => {
+                write!(f, "invalid certificate: {source}")
+            }
+            TlsError::InvalidPrivateKey { source } => {
+                write!(f, "invalid private key: {source}")
+            }
+            TlsError::CertificateExpired { peer } => {
+                write!(f, "peer certificate expired")?;
+                match peer {
+                    Some(peer) => write!(f, " ({peer})"),
+                    None => Ok(()),
+                }
+            }
+            TlsError::UnknownIssuer { peer } => {
+                write!(f, "peer certificate signed by an unknown issuer")?;
+                match peer {
+                    Some(peer) => write!(f, " ({peer})"),
+                    None => Ok(()),
+                }
+            }
+            TlsError::ClientCertRequired { peer } => {
+                write!(f, "client certificate required")?;
+                match peer {
+                    Some(peer) => write!(f, " ({peer})"),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl StdError for TlsError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            TlsError::HandshakeFailed { source, .. } => Some(source.as_ref()),
+            TlsError::InvalidCertificate { source } => Some(source.as_ref()),
+            TlsError::InvalidPrivateKey { source } => Some(source.as_ref()),
+            TlsError::CertificateExpired { .. } => None,
+            TlsError::UnknownIssuer { .. } => None,
+            TlsError::ClientCertRequired { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,294 @@
+//! OpenSSL-backed TLS connector/acceptor.
+//!
+//! This mirrors [`super::rustls`]'s connector/acceptor as closely as
+//! possible so that [`ClientTlsConfig`] and [`ServerTlsConfig`] work
+//! unchanged regardless of which backend is enabled. Prefer this backend
+//! when the deployment environment requires linking against a specific
+//! (often FIPS-validated) OpenSSL build rather than the compiled-in
+//! `rustls` stack.
+//!
+//! [`ClientTlsConfig`]: crate::transport::ClientTlsConfig
+//! [`ServerTlsConfig`]: crate::transport::ServerTlsConfig
+
+use std::net::SocketAddr;
+
+use openssl::pkey::PKey;
+use openssl::ssl::{Ssl, SslAcceptor, SslConnector, SslMethod, SslVerifyMode};
+use openssl::x509::{X509VerifyResult, X509};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_openssl::SslStream;
+
+use super::TlsError;
+use crate::transport::{channel::ClientTlsConfig, server::ServerTlsConfig, Error};
+
+/// Which side of the handshake `classify_handshake_error` is classifying
+/// for. A missing peer certificate only means "client didn't send one" on
+/// the server side; on the client side it's the default, unremarkable state
+/// for most of a handshake and tells us nothing about *why* it failed.
+enum Role {
+    Client,
+    Server,
+}
+
+/// Classifies a failed handshake on `ssl` into the matching [`TlsError`].
+///
+/// Unlike rustls, openssl doesn't give us a typed error enum to match on;
+/// the verify result on the `Ssl` is the most reliable signal for *why* a
+/// certificate was rejected, so inspect that first and fall back to the
+/// handshake error itself.
+fn classify_handshake_error(
+    ssl: &openssl::ssl::SslRef,
+    role: Role,
+    source: impl std::error::Error + Send + Sync + 'static,
+    peer: Option<SocketAddr>,
+) -> TlsError {
+    match ssl.verify_result() {
+        X509VerifyResult::X509_V_OK => {}
+        X509VerifyResult::CERT_HAS_EXPIRED | X509VerifyResult::CERT_NOT_YET_VALID => {
+            return TlsError::CertificateExpired { peer }
+        }
+        result
+            if matches!(
+                result,
+                X509VerifyResult::UNABLE_TO_GET_ISSUER_CERT
+                    | X509VerifyResult::UNABLE_TO_GET_ISSUER_CERT_LOCALLY
+                    | X509VerifyResult::SELF_SIGNED_CERT_IN_CHAIN
+                    | X509VerifyResult::DEPTH_ZERO_SELF_SIGNED_CERT
+            ) =>
+        {
+            return TlsError::UnknownIssuer { peer }
+        }
+        _ => {}
+    }
+
+    // A missing peer certificate is only meaningful on the server side,
+    // where it means a required client certificate never arrived. On the
+    // client side `peer_certificate()` is `None` for most early handshake
+    // failures (reset, protocol/cipher mismatch, timeout) that have
+    // nothing to do with client certs, so don't misclassify those.
+    if matches!(role, Role::Server)
+        && ssl.peer_certificate().is_none()
+        && ssl.verify_mode().contains(SslVerifyMode::PEER)
+    {
+        return TlsError::ClientCertRequired { peer };
+    }
+
+    TlsError::HandshakeFailed {
+        peer,
+        source: Box::new(source),
+    }
+}
+
+pub(crate) struct TlsConnector {
+    pub(crate) connector: SslConnector,
+    pub(crate) domain: String,
+}
+
+impl TlsConnector {
+    pub(crate) fn new(config: &ClientTlsConfig) -> crate::transport::Result<Self> {
+        let mut builder = SslConnector::builder(SslMethod::tls_client())
+            .map_err(|e| Error::from_source(format!("failed to init openssl connector: {e}")))?;
+
+        if config.with_webpki_roots {
+            // `webpki-roots` only ships trust anchors (subject + SPKI), not
+            // full self-signed certificates, so there's nothing here that
+            // OpenSSL's `X509Store` (which needs actual `X509` certs) can
+            // consume. Fail clearly instead of silently building a store
+            // with zero trusted roots in it.
+            return Err(Error::from_source(
+                "with_webpki_roots() is not supported by the tls-openssl backend; \
+                 use with_native_roots() or ca_certificate() instead",
+            ));
+        }
+
+        if config.with_native_roots {
+            // OpenSSL already knows how to load the platform's default
+            // trust store; there's no need to parse it ourselves.
+            builder
+                .set_default_verify_paths()
+                .map_err(|e| Error::from_source(format!("failed to load native roots: {e}")))?;
+        }
+
+        for cert in &config.ca_certs {
+            let x509 = X509::from_pem(&cert.pem).map_err(|e| {
+                Error::from(TlsError::InvalidCertificate {
+                    source: Box::new(e),
+                })
+            })?;
+            builder.cert_store_mut().add_cert(x509).map_err(|e| {
+                Error::from(TlsError::InvalidCertificate {
+                    source: Box::new(e),
+                })
+            })?;
+        }
+
+        if let Some(identity) = &config.identity {
+            let cert = X509::from_pem(&identity.cert_pem).map_err(|e| {
+                Error::from(TlsError::InvalidCertificate {
+                    source: Box::new(e),
+                })
+            })?;
+            let key = PKey::private_key_from_pem(&identity.key_pem).map_err(|e| {
+                Error::from(TlsError::InvalidPrivateKey {
+                    source: Box::new(e),
+                })
+            })?;
+
+            builder.set_certificate(&cert).map_err(|e| {
+                Error::from(TlsError::InvalidCertificate {
+                    source: Box::new(e),
+                })
+            })?;
+            builder.set_private_key(&key).map_err(|e| {
+                Error::from(TlsError::InvalidPrivateKey {
+                    source: Box::new(e),
+                })
+            })?;
+        }
+
+        let domain = config
+            .domain
+            .clone()
+            .ok_or_else(|| Error::from_source("no domain name set for TLS connector"))?;
+
+        Ok(TlsConnector {
+            connector: builder.build(),
+            domain,
+        })
+    }
+
+    /// Performs the TLS handshake over `io`, classifying a failed handshake
+    /// into the matching [`TlsError`].
+    pub(crate) async fn handshake<IO>(
+        &self,
+        io: IO,
+        peer: Option<SocketAddr>,
+    ) -> crate::transport::Result<SslStream<IO>>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        let ssl = self
+            .connector
+            .configure()
+            .and_then(|conf| conf.into_ssl(&self.domain))
+            .map_err(|e| Error::from_source(format!("failed to configure openssl session: {e}")))?;
+
+        let mut stream = SslStream::new(ssl, io)
+            .map_err(|e| Error::from_source(format!("failed to create openssl stream: {e}")))?;
+
+        match std::pin::Pin::new(&mut stream).connect().await {
+            Ok(()) => Ok(stream),
+            Err(e) => Err(Error::from(classify_handshake_error(
+                stream.ssl(),
+                Role::Client,
+                e,
+                peer,
+            ))),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct TlsAcceptor {
+    pub(crate) acceptor: SslAcceptor,
+}
+
+impl TlsAcceptor {
+    pub(crate) fn new(config: &ServerTlsConfig) -> crate::transport::Result<Self> {
+        let identity = config
+            .identity
+            .as_ref()
+            .ok_or_else(|| Error::from_source("no identity set for TLS acceptor"))?;
+
+        let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls_server())
+            .map_err(|e| Error::from_source(format!("failed to init openssl acceptor: {e}")))?;
+
+        let cert = X509::from_pem(&identity.cert_pem).map_err(|e| {
+            Error::from(TlsError::InvalidCertificate {
+                source: Box::new(e),
+            })
+        })?;
+        let key = PKey::private_key_from_pem(&identity.key_pem).map_err(|e| {
+            Error::from(TlsError::InvalidPrivateKey {
+                source: Box::new(e),
+            })
+        })?;
+
+        builder.set_certificate(&cert).map_err(|e| {
+            Error::from(TlsError::InvalidCertificate {
+                source: Box::new(e),
+            })
+        })?;
+        builder.set_private_key(&key).map_err(|e| {
+            Error::from(TlsError::InvalidPrivateKey {
+                source: Box::new(e),
+            })
+        })?;
+
+        if let Some(ca_root) = &config.client_ca_root {
+            let ca_cert = X509::from_pem(&ca_root.pem).map_err(|e| {
+                Error::from(TlsError::InvalidCertificate {
+                    source: Box::new(e),
+                })
+            })?;
+            builder.cert_store_mut().add_cert(ca_cert).map_err(|e| {
+                Error::from(TlsError::InvalidCertificate {
+                    source: Box::new(e),
+                })
+            })?;
+
+            let mut mode = SslVerifyMode::PEER;
+            if !config.client_auth_optional {
+                mode |= SslVerifyMode::FAIL_IF_NO_PEER_CERT;
+            }
+            builder.set_verify(mode);
+        } else {
+            builder.set_verify(SslVerifyMode::NONE);
+        }
+
+        Ok(TlsAcceptor {
+            acceptor: builder.build(),
+        })
+    }
+
+    /// Performs the TLS handshake over `io`, classifying a failed handshake
+    /// into the matching [`TlsError`].
+    pub(crate) async fn handshake<IO>(
+        &self,
+        io: IO,
+        peer: Option<SocketAddr>,
+    ) -> crate::transport::Result<SslStream<IO>>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        let ssl = Ssl::new(self.acceptor.context())
+            .map_err(|e| Error::from_source(format!("failed to configure openssl session: {e}")))?;
+
+        let mut stream = SslStream::new(ssl, io)
+            .map_err(|e| Error::from_source(format!("failed to create openssl stream: {e}")))?;
+
+        match std::pin::Pin::new(&mut stream).accept().await {
+            Ok(()) => Ok(stream),
+            Err(e) => Err(Error::from(classify_handshake_error(
+                stream.ssl(),
+                Role::Server,
+                e,
+                peer,
+            ))),
+        }
+    }
+}
+
+/// Extracts the verified client certificate chain from a completed
+/// handshake, for the connection handler to insert into request
+/// extensions as [`super::PeerCertificates`].
+pub(crate) fn peer_certificates<S>(
+    stream: &tokio_openssl::SslStream<S>,
+) -> Option<super::PeerCertificates> {
+    let chain = stream.ssl().verified_chain()?;
+    let der_chain = chain
+        .iter()
+        .filter_map(|cert| cert.to_der().ok())
+        .collect();
+    Some(super::PeerCertificates::new(der_chain))
+}
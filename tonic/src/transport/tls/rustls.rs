@@ -0,0 +1,247 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{CertificateError, ClientConfig, RootCertStore, ServerConfig};
+
+use super::{Certificate, TlsError};
+use crate::transport::{channel::ClientTlsConfig, server::ServerTlsConfig, Error};
+
+/// Turns the I/O error a failed rustls handshake returns back into the
+/// [`rustls::Error`](tokio_rustls::rustls::Error) that caused it (rustls
+/// wraps it via `io::Error::new(ErrorKind::Other, ...)`), and classifies
+/// that into the matching [`TlsError`] variant.
+fn classify_handshake_error(e: std::io::Error, peer: Option<SocketAddr>) -> TlsError {
+    let kind = e.kind();
+    let message = e.to_string();
+    let Some(inner) = e.into_inner() else {
+        return TlsError::HandshakeFailed {
+            peer,
+            source: Box::new(std::io::Error::new(kind, message)),
+        };
+    };
+
+    match inner.downcast::<tokio_rustls::rustls::Error>() {
+        Ok(rustls_err) => match *rustls_err {
+            tokio_rustls::rustls::Error::InvalidCertificate(
+                CertificateError::Expired | CertificateError::NotValidYet,
+            ) => {
+                TlsError::CertificateExpired { peer }
+            }
+            tokio_rustls::rustls::Error::InvalidCertificate(CertificateError::UnknownIssuer) => {
+                TlsError::UnknownIssuer { peer }
+            }
+            tokio_rustls::rustls::Error::NoCertificatesPresented => {
+                TlsError::ClientCertRequired { peer }
+            }
+            other => TlsError::HandshakeFailed {
+                peer,
+                source: Box::new(other),
+            },
+        },
+        Err(inner) => TlsError::HandshakeFailed { peer, source: inner },
+    }
+}
+
+fn into_certificate_der(cert: &Certificate) -> crate::transport::Result<Vec<CertificateDer<'static>>> {
+    rustls_pemfile::certs(&mut cert.pem.as_ref())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| {
+            Error::from(TlsError::InvalidCertificate {
+                source: Box::new(e),
+            })
+        })
+}
+
+pub(crate) struct TlsConnector {
+    pub(crate) config: Arc<ClientConfig>,
+    pub(crate) domain: ServerName<'static>,
+}
+
+impl TlsConnector {
+    pub(crate) fn new(config: &ClientTlsConfig) -> crate::transport::Result<Self> {
+        let domain = config
+            .domain
+            .clone()
+            .ok_or_else(|| Error::from_source("no domain name set for TLS connector"))?;
+        let domain = ServerName::try_from(domain)
+            .map_err(|e| Error::from_source(format!("invalid domain name: {e}")))?;
+
+        if let Some(rustls_config) = &config.rustls_client_config {
+            return Ok(TlsConnector {
+                config: rustls_config.clone(),
+                domain,
+            });
+        }
+
+        let mut roots = RootCertStore::empty();
+
+        if config.with_webpki_roots {
+            roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        }
+
+        if config.with_native_roots {
+            for cert in rustls_native_certs::load_native_certs().certs {
+                // Native stores often contain certificates rustls can't
+                // parse (e.g. expired roots); skip those instead of
+                // failing the whole connector.
+                let _ = roots.add(cert);
+            }
+        }
+
+        for cert in &config.ca_certs {
+            for der in into_certificate_der(cert)? {
+                roots.add(der).map_err(|e| {
+                    Error::from(TlsError::InvalidCertificate {
+                        source: Box::new(e),
+                    })
+                })?;
+            }
+        }
+
+        let builder = ClientConfig::builder().with_root_certificates(roots);
+
+        let tls_config = if let Some(identity) = &config.identity {
+            let certs = into_certificate_der(&Certificate::from_pem(&identity.cert_pem))?;
+            let key = rustls_pemfile::private_key(&mut identity.key_pem.as_ref())
+                .map_err(|e| {
+                    Error::from(TlsError::InvalidPrivateKey {
+                        source: Box::new(e),
+                    })
+                })?
+                .ok_or_else(|| {
+                    Error::from(TlsError::InvalidPrivateKey {
+                        source: "no private key found".into(),
+                    })
+                })?;
+
+            builder.with_client_auth_cert(certs, key).map_err(|e| {
+                Error::from(TlsError::InvalidCertificate {
+                    source: Box::new(e),
+                })
+            })?
+        } else {
+            builder.with_no_client_auth()
+        };
+
+        Ok(TlsConnector {
+            config: Arc::new(tls_config),
+            domain,
+        })
+    }
+
+    /// Performs the TLS handshake over `io`, classifying a failed handshake
+    /// into the matching [`TlsError`].
+    pub(crate) async fn handshake<IO>(
+        &self,
+        io: IO,
+        peer: Option<SocketAddr>,
+    ) -> crate::transport::Result<tokio_rustls::client::TlsStream<IO>>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        tokio_rustls::TlsConnector::from(self.config.clone())
+            .connect(self.domain.clone(), io)
+            .await
+            .map_err(|e| Error::from(classify_handshake_error(e, peer)))
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct TlsAcceptor {
+    pub(crate) config: Arc<ServerConfig>,
+}
+
+impl TlsAcceptor {
+    pub(crate) fn new(config: &ServerTlsConfig) -> crate::transport::Result<Self> {
+        if let Some(rustls_config) = &config.rustls_server_config {
+            return Ok(TlsAcceptor {
+                config: rustls_config.clone(),
+            });
+        }
+
+        let identity = config
+            .identity
+            .as_ref()
+            .ok_or_else(|| Error::from_source("no identity set for TLS acceptor"))?;
+
+        let certs = into_certificate_der(&Certificate::from_pem(&identity.cert_pem))?;
+        let key: PrivateKeyDer<'static> = rustls_pemfile::private_key(&mut identity.key_pem.as_ref())
+            .map_err(|e| {
+                Error::from(TlsError::InvalidPrivateKey {
+                    source: Box::new(e),
+                })
+            })?
+            .ok_or_else(|| {
+                Error::from(TlsError::InvalidPrivateKey {
+                    source: "no private key found".into(),
+                })
+            })?;
+
+        let client_verifier = if let Some(ca_root) = &config.client_ca_root {
+            let mut roots = RootCertStore::empty();
+            for der in into_certificate_der(ca_root)? {
+                roots.add(der).map_err(|e| {
+                    Error::from(TlsError::InvalidCertificate {
+                        source: Box::new(e),
+                    })
+                })?;
+            }
+
+            let builder = WebPkiClientVerifier::builder(Arc::new(roots));
+            let builder = if config.client_auth_optional {
+                builder.allow_unauthenticated()
+            } else {
+                builder
+            };
+
+            builder
+                .build()
+                .map_err(|e| Error::from_source(format!("invalid client ca root: {e}")))?
+        } else {
+            WebPkiClientVerifier::no_client_auth()
+        };
+
+        let tls_config = ServerConfig::builder()
+            .with_client_cert_verifier(client_verifier)
+            .with_single_cert(certs, key)
+            .map_err(|e| {
+                Error::from(TlsError::InvalidCertificate {
+                    source: Box::new(e),
+                })
+            })?;
+
+        Ok(TlsAcceptor {
+            config: Arc::new(tls_config),
+        })
+    }
+
+    /// Performs the TLS handshake over `io`, classifying a failed handshake
+    /// into the matching [`TlsError`].
+    pub(crate) async fn handshake<IO>(
+        &self,
+        io: IO,
+        peer: Option<SocketAddr>,
+    ) -> crate::transport::Result<tokio_rustls::server::TlsStream<IO>>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        tokio_rustls::TlsAcceptor::from(self.config.clone())
+            .accept(io)
+            .await
+            .map_err(|e| Error::from(classify_handshake_error(e, peer)))
+    }
+}
+
+/// Extracts the verified client certificate chain from a completed
+/// handshake, for the connection handler to insert into request
+/// extensions as [`super::PeerCertificates`].
+pub(crate) fn peer_certificates(
+    conn: &tokio_rustls::rustls::ServerConnection,
+) -> Option<super::PeerCertificates> {
+    conn.peer_certificates().map(|certs| {
+        super::PeerCertificates::new(certs.iter().map(|c| c.as_ref().to_vec()).collect())
+    })
+}